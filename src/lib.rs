@@ -28,17 +28,32 @@ pub enum Error {
   #[error("failed to read contents of file at {path} | {e:?}")]
   ReadFileContents { e: std::io::Error, path: PathBuf },
 
-  #[error("failed to parse toml file at {path} | {e:?}")]
-  ParseToml { e: toml::de::Error, path: PathBuf },
+  #[error("failed to parse toml file at {path} | at {key_path} | {e:?}")]
+  ParseToml {
+    e: Box<toml::de::Error>,
+    path: PathBuf,
+    key_path: String,
+  },
+
+  #[error("failed to parse json file at {path} | at {key_path} | {e:?}")]
+  ParseJson {
+    e: serde_json::Error,
+    path: PathBuf,
+    key_path: String,
+  },
 
-  #[error("failed to parse json file at {path} | {e:?}")]
-  ParseJson { e: serde_json::Error, path: PathBuf },
+  #[error("failed to parse yaml file at {path} | at {key_path} | {e:?}")]
+  ParseYaml {
+    e: serde_yaml::Error,
+    path: PathBuf,
+    key_path: String,
+  },
 
   #[error("unsupported file type at {path}")]
   UnsupportedFileType { path: PathBuf },
 
-  #[error("failed to parse merged config into final type | {e:?}")]
-  ParseFinalJson { e: serde_json::Error },
+  #[error("failed to parse merged config into final type | at {path} | {e:?}")]
+  ParseFinalJson { e: serde_json::Error, path: String },
 
   #[error("failed to serialize merged config to string | {e:?}")]
   SerializeFinalJson { e: serde_json::Error },
@@ -54,10 +69,60 @@ pub enum Error {
 
   #[error("failed to get metadata for path {path:?} | {e:?}")]
   ReadPathMetaData { path: PathBuf, e: std::io::Error },
+
+  #[error("invalid override pair '{pair}', expected 'key=value'")]
+  InvalidOverridePair { pair: String },
+
+  #[error("found {} unknown config key(s) | {keys:?}", keys.len())]
+  UnknownConfigKeys { keys: Vec<String> },
+
+  #[error("conflicting values on field {key} | got {existing:?} and {incoming:?}")]
+  ScalarConflict {
+    key: String,
+    existing: Box<dyn std::fmt::Debug>,
+    incoming: Box<dyn std::fmt::Debug>,
+  },
+
+  #[error("failed to write depfile at {path} | {e:?}")]
+  WriteDepfile { e: std::io::Error, path: PathBuf },
 }
 
 pub type Result<T> = ::core::result::Result<T, Error>;
 
+/// policy used by [merge_objects] when two sources are merged.
+/// - `merge_nested`: recurse into matching object fields instead of replacing them wholesale
+/// - `extend_array`: append matching array fields instead of replacing them wholesale
+/// - `dedup_arrays`: when extending arrays, skip elements already present in the target
+/// - `strict_scalar`: error instead of last-wins when two sources disagree on a scalar field
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeOptions {
+  pub merge_nested: bool,
+  pub extend_array: bool,
+  pub dedup_arrays: bool,
+  pub strict_scalar: bool,
+}
+
+impl MergeOptions {
+  pub fn new(merge_nested: bool, extend_array: bool) -> Self {
+    Self {
+      merge_nested,
+      extend_array,
+      ..Default::default()
+    }
+  }
+
+  /// options to use when overlaying a highest-priority source (env / inline overrides),
+  /// which should always merge into nested objects regardless of the caller's file-merge policy,
+  /// and whose scalar values replace file values even when `strict_scalar` is set
+  fn force_merge_nested(self) -> Self {
+    Self {
+      merge_nested: true,
+      strict_scalar: false,
+      ..self
+    }
+  }
+}
+
 /// parse paths that are either directories or files
 pub fn parse_config_paths<'a, T: DeserializeOwned>(
   paths: &[&Path],
@@ -65,6 +130,154 @@ pub fn parse_config_paths<'a, T: DeserializeOwned>(
   merge_nested: bool,
   extend_array: bool,
 ) -> Result<T> {
+  parse_config_paths_with_options(
+    paths,
+    match_wildcards,
+    MergeOptions::new(merge_nested, extend_array),
+  )
+}
+
+/// same as [parse_config_paths], taking a full [MergeOptions] rather than the two
+/// most common policy bits
+pub fn parse_config_paths_with_options<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  options: MergeOptions,
+) -> Result<T> {
+  let paths = expand_config_paths(paths, match_wildcards)?;
+  parse_config_files_with_options(&paths, options)
+}
+
+/// same as [parse_config_paths_with_env_and_options], taking the two most common
+/// policy bits rather than a full [MergeOptions]
+pub fn parse_config_paths_with_env<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  merge_nested: bool,
+  extend_array: bool,
+  env_prefix: &str,
+  env_delimiter: &str,
+) -> Result<T> {
+  parse_config_paths_with_env_and_options(
+    paths,
+    match_wildcards,
+    MergeOptions::new(merge_nested, extend_array),
+    env_prefix,
+    env_delimiter,
+  )
+}
+
+/// Same as [parse_config_paths], but after the file layers are merged, overlays
+/// environment variables as the highest-priority source. Env vars are matched by
+/// `env_prefix`, the prefix is stripped, the remainder is lowercased and split on
+/// `env_delimiter` to build a nested object, eg with prefix `CONF_` and delimiter `__`,
+/// `CONF_DATABASE__POOL__SIZE=10` becomes `{"database":{"pool":{"size":10}}}`.
+/// Values are parsed opportunistically (json number / bool / array) falling back to string.
+pub fn parse_config_paths_with_env_and_options<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  options: MergeOptions,
+  env_prefix: &str,
+  env_delimiter: &str,
+) -> Result<T> {
+  let paths = expand_config_paths(paths, match_wildcards)?;
+  let target = merge_files_into_map(&paths, &options)?;
+  let env = env_overlay_map(env_prefix, env_delimiter)?;
+  let target = merge_objects(target, env, &options.force_merge_nested())?;
+  finalize_config(target)
+}
+
+/// same as [parse_config_with_overrides_and_options], taking the two most common
+/// policy bits rather than a full [MergeOptions]
+pub fn parse_config_with_overrides<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  merge_nested: bool,
+  extend_array: bool,
+  override_str: &str,
+) -> Result<T> {
+  parse_config_with_overrides_and_options(
+    paths,
+    match_wildcards,
+    MergeOptions::new(merge_nested, extend_array),
+    override_str,
+  )
+}
+
+/// Same as [parse_config_paths], but layers an ad-hoc `override_str` on top of the
+/// merged file config, so CLI/runtime values win. `override_str` is interpreted in the
+/// first mode that matches:
+/// 1. an existing file path, parsed as a config file
+/// 2. a JSON object, used directly
+/// 3. comma-separated `key=value` pairs, where `key` may be dot-nested
+///    (`server.port=8080,server.host=localhost`). A value containing a comma must be
+///    a quoted string or a JSON array, since top-level commas separate pairs
+///    (`tags=[1,2,3]`, `name="hello,world"`).
+pub fn parse_config_with_overrides_and_options<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  options: MergeOptions,
+  override_str: &str,
+) -> Result<T> {
+  let paths = expand_config_paths(paths, match_wildcards)?;
+  let target = merge_files_into_map(&paths, &options)?;
+  let overrides = parse_override_str(override_str)?;
+  let target = merge_objects(target, overrides, &options.force_merge_nested())?;
+  finalize_config(target)
+}
+
+fn parse_override_str(override_str: &str) -> Result<Map<String, Value>> {
+  let path = Path::new(override_str);
+  if path.is_file() {
+    return parse_config_file(path);
+  }
+
+  if let Ok(Value::Object(map)) = serde_json::from_str::<Value>(override_str) {
+    return Ok(map);
+  }
+
+  let mut target = Map::new();
+  for pair in split_top_level_commas(override_str) {
+    let parts = pair.split('=').collect::<Vec<_>>();
+    let [key, value] = parts[..] else {
+      return Err(Error::InvalidOverridePair {
+        pair: pair.to_string(),
+      });
+    };
+    let keys = key.split('.').map(String::from).collect::<Vec<_>>();
+    insert_nested(&mut target, &keys, parse_opportunistic_value(value))?;
+  }
+  Ok(target)
+}
+
+/// splits `s` on `,` characters that are not nested inside a `"..."` string or a
+/// `[...]`/`{...}` structure, so a JSON array or quoted string value in a
+/// comma-separated `key=value` list isn't torn apart at its own internal commas
+fn split_top_level_commas(s: &str) -> Vec<&str> {
+  let mut parts = Vec::new();
+  let mut depth = 0i32;
+  let mut in_string = false;
+  let mut start = 0;
+  for (i, c) in s.char_indices() {
+    match c {
+      '"' => in_string = !in_string,
+      '[' | '{' if !in_string => depth += 1,
+      ']' | '}' if !in_string => depth -= 1,
+      ',' if !in_string && depth == 0 => {
+        parts.push(&s[start..i]);
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  parts.push(&s[start..]);
+  parts
+}
+
+fn expand_config_paths<'a>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+) -> Result<Vec<PathBuf>> {
   let match_wildcards = match_wildcards
     .map(|match_wildcards| {
       match_wildcards
@@ -83,26 +296,80 @@ pub fn parse_config_paths<'a, T: DeserializeOwned>(
       }
     })
     .collect::<Vec<_>>();
-  let paths = paths
-    .into_iter()
-    .map(|&path| {
-      let is_dir = std::fs::metadata(path)
-        .map_err(|e| Error::ReadPathMetaData {
-          path: path.to_path_buf(),
-          e,
-        })?
-        .is_dir();
-      if is_dir {
-        file_names_in_dir(path, &wildcards)
-      } else {
-        Result::Ok(vec![path.to_path_buf()])
-      }
-    })
-    .collect::<Result<Vec<_>>>()?
-    .into_iter()
-    .flatten()
-    .collect::<Vec<PathBuf>>();
-  parse_config_files(&paths, merge_nested, extend_array)
+  Ok(
+    paths
+      .into_iter()
+      .map(|&path| {
+        let is_dir = std::fs::metadata(path)
+          .map_err(|e| Error::ReadPathMetaData {
+            path: path.to_path_buf(),
+            e,
+          })?
+          .is_dir();
+        if is_dir {
+          file_names_in_dir(path, &wildcards)
+        } else {
+          Result::Ok(vec![path.to_path_buf()])
+        }
+      })
+      .collect::<Result<Vec<_>>>()?
+      .into_iter()
+      .flatten()
+      .collect::<Vec<PathBuf>>(),
+  )
+}
+
+/// builds a nested [Map] from environment variables matching `prefix`,
+/// stripping the prefix and splitting the remainder (lowercased) on `delimiter`
+fn env_overlay_map(prefix: &str, delimiter: &str) -> Result<Map<String, Value>> {
+  let mut target = Map::new();
+  for (key, value) in std::env::vars() {
+    let Some(stripped) = key.strip_prefix(prefix) else {
+      continue;
+    };
+    let keys = stripped
+      .to_lowercase()
+      .split(delimiter)
+      .map(String::from)
+      .collect::<Vec<_>>();
+    insert_nested(&mut target, &keys, parse_opportunistic_value(&value))?;
+  }
+  Ok(target)
+}
+
+/// inserts `value` into `target` at the path described by `keys`, creating
+/// intermediate objects as needed
+fn insert_nested(target: &mut Map<String, Value>, keys: &[String], value: Value) -> Result<()> {
+  let [key, rest @ ..] = keys else {
+    return Ok(());
+  };
+  if rest.is_empty() {
+    if let Some(Value::Object(_)) = target.get(key) {
+      return Err(Error::ObjectFieldTypeMismatch {
+        key: key.clone(),
+        value: Box::new(value),
+      });
+    }
+    target.insert(key.clone(), value);
+    return Ok(());
+  }
+  let entry = target
+    .entry(key.clone())
+    .or_insert_with(|| Value::Object(Map::new()));
+  let Value::Object(nested) = entry else {
+    return Err(Error::ObjectFieldTypeMismatch {
+      key: key.clone(),
+      value: Box::new(entry.clone()),
+    });
+  };
+  insert_nested(nested, rest, value)
+}
+
+/// parses a raw string value opportunistically, trying json (number / bool / array / object)
+/// first and falling back to a plain string. used for env vars and inline overrides, where
+/// values always arrive as strings but typed fields (eg `port: u16`) still need to deserialize
+fn parse_opportunistic_value(value: &str) -> Value {
+  serde_json::from_str::<Value>(value).unwrap_or_else(|_| Value::String(value.to_string()))
 }
 
 /// will sort file names alphabetically
@@ -155,66 +422,237 @@ pub fn parse_config_files<T: DeserializeOwned>(
   merge_nested: bool,
   extend_array: bool,
 ) -> Result<T> {
+  parse_config_files_with_options(paths, MergeOptions::new(merge_nested, extend_array))
+}
+
+/// same as [parse_config_files], taking a full [MergeOptions] rather than the two
+/// most common policy bits
+pub fn parse_config_files_with_options<T: DeserializeOwned>(
+  paths: &[PathBuf],
+  options: MergeOptions,
+) -> Result<T> {
+  let target = merge_files_into_map(paths, &options)?;
+  finalize_config(target)
+}
+
+/// same as [parse_config_paths], but also collects every key present in the merged
+/// config but absent from `T`, which the current silent-merge path otherwise hides.
+/// if `strict` is set, returns `Error::UnknownConfigKeys` when any are found, instead
+/// of returning them alongside the parsed config.
+pub fn parse_config_paths_checked<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  merge_nested: bool,
+  extend_array: bool,
+  strict: bool,
+) -> Result<(T, Vec<String>)> {
+  parse_config_paths_checked_with_options(
+    paths,
+    match_wildcards,
+    MergeOptions::new(merge_nested, extend_array),
+    strict,
+  )
+}
+
+/// same as [parse_config_paths_checked], taking a full [MergeOptions] rather than the
+/// two most common policy bits
+pub fn parse_config_paths_checked_with_options<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  options: MergeOptions,
+  strict: bool,
+) -> Result<(T, Vec<String>)> {
+  let paths = expand_config_paths(paths, match_wildcards)?;
+  parse_config_files_checked_with_options(&paths, options, strict)
+}
+
+/// same as [parse_config_paths], but also returns every concrete file that contributed
+/// to the final merged config (after directory/wildcard expansion, in merge order).
+/// when `depfile_path` is set, the same list is additionally written there, one path
+/// per line, for build systems driving cache invalidation off it.
+pub fn parse_config_paths_tracked<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  merge_nested: bool,
+  extend_array: bool,
+  depfile_path: Option<&Path>,
+) -> Result<(T, Vec<PathBuf>)> {
+  parse_config_paths_tracked_with_options(
+    paths,
+    match_wildcards,
+    MergeOptions::new(merge_nested, extend_array),
+    depfile_path,
+  )
+}
+
+/// same as [parse_config_paths_tracked], taking a full [MergeOptions] rather than the
+/// two most common policy bits
+pub fn parse_config_paths_tracked_with_options<'a, T: DeserializeOwned>(
+  paths: &[&Path],
+  match_wildcards: Option<&'a [&'a str]>,
+  options: MergeOptions,
+  depfile_path: Option<&Path>,
+) -> Result<(T, Vec<PathBuf>)> {
+  let paths = expand_config_paths(paths, match_wildcards)?;
+  let config = parse_config_files_with_options(&paths, options)?;
+  if let Some(depfile_path) = depfile_path {
+    write_depfile(depfile_path, &paths)?;
+  }
+  Ok((config, paths))
+}
+
+fn write_depfile(path: &Path, paths: &[PathBuf]) -> Result<()> {
+  let contents = paths
+    .iter()
+    .map(|path| path.to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("\n");
+  std::fs::write(path, contents).map_err(|e| Error::WriteDepfile {
+    e,
+    path: path.to_path_buf(),
+  })
+}
+
+/// same as [parse_config_files], but also collects every key present in the merged
+/// config but absent from `T`, which the current silent-merge path otherwise hides.
+/// if `strict` is set, returns `Error::UnknownConfigKeys` when any are found, instead
+/// of returning them alongside the parsed config.
+pub fn parse_config_files_checked<T: DeserializeOwned>(
+  paths: &[PathBuf],
+  merge_nested: bool,
+  extend_array: bool,
+  strict: bool,
+) -> Result<(T, Vec<String>)> {
+  parse_config_files_checked_with_options(
+    paths,
+    MergeOptions::new(merge_nested, extend_array),
+    strict,
+  )
+}
+
+/// same as [parse_config_files_checked], taking a full [MergeOptions] rather than the
+/// two most common policy bits
+pub fn parse_config_files_checked_with_options<T: DeserializeOwned>(
+  paths: &[PathBuf],
+  options: MergeOptions,
+  strict: bool,
+) -> Result<(T, Vec<String>)> {
+  let target = merge_files_into_map(paths, &options)?;
+  finalize_config_checked(target, strict)
+}
+
+/// parses and merges multiple config files into a single [Map], without
+/// deserializing into the final type
+fn merge_files_into_map(paths: &[PathBuf], options: &MergeOptions) -> Result<Map<String, Value>> {
   let mut target = Map::new();
 
   for path in paths {
-    target = merge_objects(
-      target,
-      parse_config_file(path.borrow())?,
-      merge_nested,
-      extend_array,
-    )?;
+    target = merge_objects(target, parse_config_file(path.borrow())?, options)?;
   }
 
-  serde_json::from_str(
-    &serde_json::to_string(&target).map_err(|e| Error::SerializeFinalJson { e })?,
-  )
-  .map_err(|e| Error::ParseFinalJson { e })
+  Ok(target)
+}
+
+/// deserializes a merged config [Map] into the final type
+fn finalize_config<T: DeserializeOwned>(target: Map<String, Value>) -> Result<T> {
+  let json = serde_json::to_string(&target).map_err(|e| Error::SerializeFinalJson { e })?;
+  let mut de = serde_json::Deserializer::from_str(&json);
+  serde_path_to_error::deserialize(&mut de).map_err(|e| Error::ParseFinalJson {
+    path: e.path().to_string(),
+    e: e.into_inner(),
+  })
+}
+
+/// same as [finalize_config], but also collects every key present in `target` but
+/// absent from `T`, and returns `Error::UnknownConfigKeys` instead if `strict` is set
+fn finalize_config_checked<T: DeserializeOwned>(
+  target: Map<String, Value>,
+  strict: bool,
+) -> Result<(T, Vec<String>)> {
+  let json = serde_json::to_string(&target).map_err(|e| Error::SerializeFinalJson { e })?;
+  let mut unknown = Vec::new();
+  let mut de = serde_json::Deserializer::from_str(&json);
+  let mut cb = |path: serde_ignored::Path| unknown.push(path.to_string());
+  let ignored_de = serde_ignored::Deserializer::new(&mut de, &mut cb);
+  let config = serde_path_to_error::deserialize(ignored_de).map_err(|e| Error::ParseFinalJson {
+    path: e.path().to_string(),
+    e: e.into_inner(),
+  })?;
+  if strict && !unknown.is_empty() {
+    return Err(Error::UnknownConfigKeys { keys: unknown });
+  }
+  Ok((config, unknown))
 }
 
-/// parses a single config file
+/// parses a single config file, dispatching on its extension
 pub fn parse_config_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
-  let mut file = File::open(path).map_err(|e| Error::FileOpen {
+  match path.extension().and_then(|e| e.to_str()) {
+    Some("toml") => parse_toml_file(path),
+    Some("json") => parse_json_file(path),
+    Some("yaml" | "yml") => parse_yaml_file(path),
+    Some(_) | None => Err(Error::UnsupportedFileType {
+      path: path.to_path_buf(),
+    }),
+  }
+}
+
+fn parse_toml_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+  let mut contents = String::new();
+  File::open(path)
+    .map_err(|e| Error::FileOpen {
+      e,
+      path: path.to_path_buf(),
+    })?
+    .read_to_string(&mut contents)
+    .map_err(|e| Error::ReadFileContents {
+      e,
+      path: path.to_path_buf(),
+    })?;
+  let de = toml::Deserializer::new(&contents);
+  serde_path_to_error::deserialize(de).map_err(|e| Error::ParseToml {
+    path: path.to_path_buf(),
+    key_path: e.path().to_string(),
+    e: Box::new(e.into_inner()),
+  })
+}
+
+fn parse_json_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+  let file = File::open(path).map_err(|e| Error::FileOpen {
     e,
     path: path.to_path_buf(),
   })?;
-  let config = match path.extension().and_then(|e| e.to_str()) {
-    Some("toml") => {
-      let mut contents = String::new();
-      file
-        .read_to_string(&mut contents)
-        .map_err(|e| Error::ReadFileContents {
-          e,
-          path: path.to_path_buf(),
-        })?;
-      toml::from_str(&contents).map_err(|e| Error::ParseToml {
-        e,
-        path: path.to_path_buf(),
-      })?
-    }
-    Some("json") => serde_json::from_reader(file).map_err(|e| Error::ParseJson {
-      e,
-      path: path.to_path_buf(),
-    })?,
-    Some(_) | None => {
-      return Err(Error::UnsupportedFileType {
-        path: path.to_path_buf(),
-      });
-    }
-  };
-  Ok(config)
+  let mut de = serde_json::Deserializer::from_reader(file);
+  serde_path_to_error::deserialize(&mut de).map_err(|e| Error::ParseJson {
+    path: path.to_path_buf(),
+    key_path: e.path().to_string(),
+    e: e.into_inner(),
+  })
+}
+
+fn parse_yaml_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+  let file = File::open(path).map_err(|e| Error::FileOpen {
+    e,
+    path: path.to_path_buf(),
+  })?;
+  let de = serde_yaml::Deserializer::from_reader(file);
+  serde_path_to_error::deserialize(de).map_err(|e| Error::ParseYaml {
+    path: path.to_path_buf(),
+    key_path: e.path().to_string(),
+    e: e.into_inner(),
+  })
 }
 
 /// object is serde_json::Map<String, serde_json::Value>
 /// source will overide target
-/// will recurse when field is object if merge_object = true, otherwise object will be replaced
-/// will extend when field is array if extend_array = true, otherwise array will be replaced
-/// will return error when types on source and target fields do not match
+/// will recurse when field is object if options.merge_nested = true, otherwise object will be replaced
+/// will extend when field is array if options.extend_array = true, otherwise array will be replaced,
+/// deduping structurally-equal elements first if options.dedup_arrays = true
+/// will return error when types on source and target fields do not match, or, if
+/// options.strict_scalar = true, when source and target disagree on a scalar field
 fn merge_objects(
   mut target: Map<String, Value>,
   source: Map<String, Value>,
-  merge_nested: bool,
-  extend_array: bool,
+  options: &MergeOptions,
 ) -> Result<Map<String, Value>> {
   for (key, value) in source {
     let Some(curr) = target.remove(&key) else {
@@ -223,7 +661,7 @@ fn merge_objects(
     };
     match curr {
       Value::Object(target_obj) => {
-        if !merge_nested {
+        if !options.merge_nested {
           target.insert(key, value);
           continue;
         }
@@ -231,12 +669,7 @@ fn merge_objects(
           Value::Object(source_obj) => {
             target.insert(
               key,
-              Value::Object(merge_objects(
-                target_obj,
-                source_obj,
-                merge_nested,
-                extend_array,
-              )?),
+              Value::Object(merge_objects(target_obj, source_obj, options)?),
             );
           }
           _ => {
@@ -248,13 +681,21 @@ fn merge_objects(
         }
       }
       Value::Array(mut target_arr) => {
-        if !extend_array {
+        if !options.extend_array {
           target.insert(key, value);
           continue;
         }
         match value {
           Value::Array(source_arr) => {
-            target_arr.extend(source_arr);
+            if options.dedup_arrays {
+              for v in source_arr {
+                if !target_arr.contains(&v) {
+                  target_arr.push(v);
+                }
+              }
+            } else {
+              target_arr.extend(source_arr);
+            }
             target.insert(key, Value::Array(target_arr));
           }
           _ => {
@@ -265,10 +706,319 @@ fn merge_objects(
           }
         }
       }
-      _ => {
-        target.insert(key, value);
-      }
+      existing => match value {
+        Value::Object(_) => {
+          return Err(Error::ObjectFieldTypeMismatch {
+            key,
+            value: Box::new(existing),
+          })
+        }
+        Value::Array(_) => {
+          return Err(Error::ArrayFieldTypeMismatch {
+            key,
+            value: Box::new(existing),
+          })
+        }
+        _ => {
+          if options.strict_scalar && existing != value {
+            return Err(Error::ScalarConflict {
+              key,
+              existing: Box::new(existing),
+              incoming: Box::new(value),
+            });
+          }
+          target.insert(key, value);
+        }
+      },
     }
   }
   Ok(target)
 }
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn map(value: Value) -> Map<String, Value> {
+    let Value::Object(map) = value else {
+      panic!("expected object");
+    };
+    map
+  }
+
+  /// writes `contents` to a uniquely-named file under the system temp dir and
+  /// returns its path, for tests that need to exercise real file I/O
+  fn write_temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+      "merge_config_files_test_{}_{name}",
+      std::process::id()
+    ));
+    std::fs::write(&path, contents).unwrap();
+    path
+  }
+
+  #[test]
+  fn parse_config_paths_tracked_returns_and_writes_contributing_files() {
+    let path = write_temp_file("tracked.json", r#"{"name":"a"}"#);
+    let depfile_path = write_temp_file("tracked.d", "");
+
+    let (config, tracked): (Map<String, Value>, Vec<PathBuf>) = parse_config_paths_tracked(
+      &[path.as_path()],
+      None,
+      false,
+      false,
+      Some(&depfile_path),
+    )
+    .unwrap();
+
+    let depfile_contents = std::fs::read_to_string(&depfile_path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&depfile_path).unwrap();
+
+    assert_eq!(config["name"], json!("a"));
+    assert_eq!(tracked, vec![path.clone()]);
+    assert_eq!(depfile_contents, path.to_string_lossy());
+  }
+
+  #[test]
+  fn parse_config_files_checked_lists_unknown_keys_when_lenient() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Config {
+      #[allow(dead_code)]
+      name: String,
+    }
+
+    let path = write_temp_file("unknown_keys_lenient.json", r#"{"name":"a","extra":1}"#);
+    let (_config, unknown) = parse_config_files_checked::<Config>(
+      std::slice::from_ref(&path),
+      false,
+      false,
+      false,
+    )
+    .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(unknown, vec!["extra".to_string()]);
+  }
+
+  #[test]
+  fn parse_config_files_checked_errors_on_unknown_keys_when_strict() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Config {
+      #[allow(dead_code)]
+      name: String,
+    }
+
+    let path = write_temp_file("unknown_keys_strict.json", r#"{"name":"a","extra":1}"#);
+    let err =
+      parse_config_files_checked::<Config>(std::slice::from_ref(&path), false, false, true)
+        .unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+      err,
+      Error::UnknownConfigKeys { keys } if keys == vec!["extra".to_string()]
+    ));
+  }
+
+  #[test]
+  fn parse_json_file_reports_precise_key_path_on_type_mismatch() {
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct DbConfig {
+      replicas: Vec<Replica>,
+    }
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Replica {
+      host: String,
+    }
+
+    let path = write_temp_file(
+      "nested_type_mismatch.json",
+      r#"{"replicas":[{"host":"a"},{"host":123}]}"#,
+    );
+    let err = parse_config_file::<DbConfig>(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    let Error::ParseJson { key_path, .. } = err else {
+      panic!("expected Error::ParseJson, got {err:?}");
+    };
+    assert_eq!(key_path, "replicas[1].host");
+  }
+
+  #[test]
+  fn parse_yaml_file_reports_malformed_yaml() {
+    let path = write_temp_file("malformed.yaml", "key: [unterminated");
+    let result = parse_config_file::<Value>(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(Error::ParseYaml { .. })));
+  }
+
+  #[test]
+  fn dedup_arrays_removes_duplicates_within_source_too() {
+    let target = map(json!({ "tags": [1] }));
+    let source = map(json!({ "tags": [2, 2, 3] }));
+    let options = MergeOptions {
+      extend_array: true,
+      dedup_arrays: true,
+      ..Default::default()
+    };
+    let merged = merge_objects(target, source, &options).unwrap();
+    assert_eq!(merged["tags"], json!([1, 2, 3]));
+  }
+
+  #[test]
+  fn extend_array_without_dedup_keeps_duplicates() {
+    let target = map(json!({ "tags": [1] }));
+    let source = map(json!({ "tags": [2, 2, 3] }));
+    let options = MergeOptions {
+      extend_array: true,
+      dedup_arrays: false,
+      ..Default::default()
+    };
+    let merged = merge_objects(target, source, &options).unwrap();
+    assert_eq!(merged["tags"], json!([1, 2, 2, 3]));
+  }
+
+  #[test]
+  fn strict_scalar_errors_on_conflicting_values() {
+    let target = map(json!({ "port": 8080 }));
+    let source = map(json!({ "port": 9090 }));
+    let options = MergeOptions {
+      strict_scalar: true,
+      ..Default::default()
+    };
+    let err = merge_objects(target, source, &options).unwrap_err();
+    assert!(matches!(err, Error::ScalarConflict { key, .. } if key == "port"));
+  }
+
+  #[test]
+  fn strict_scalar_allows_agreeing_values() {
+    let target = map(json!({ "port": 8080 }));
+    let source = map(json!({ "port": 8080 }));
+    let options = MergeOptions {
+      strict_scalar: true,
+      ..Default::default()
+    };
+    let merged = merge_objects(target, source, &options).unwrap();
+    assert_eq!(merged["port"], json!(8080));
+  }
+
+  #[test]
+  fn merge_nested_off_replaces_instead_of_merging() {
+    let target = map(json!({ "database": { "host": "a", "port": 1 } }));
+    let source = map(json!({ "database": { "port": 2 } }));
+    let options = MergeOptions {
+      merge_nested: false,
+      ..Default::default()
+    };
+    let merged = merge_objects(target, source, &options).unwrap();
+    assert_eq!(merged["database"], json!({ "port": 2 }));
+  }
+
+  #[test]
+  fn merge_nested_on_recurses_into_matching_objects() {
+    let target = map(json!({ "database": { "host": "a", "port": 1 } }));
+    let source = map(json!({ "database": { "port": 2 } }));
+    let options = MergeOptions {
+      merge_nested: true,
+      ..Default::default()
+    };
+    let merged = merge_objects(target, source, &options).unwrap();
+    assert_eq!(merged["database"], json!({ "host": "a", "port": 2 }));
+  }
+
+  #[test]
+  fn override_str_parses_dot_nested_keys_with_typed_values() {
+    let overrides = parse_override_str("server.port=8080,server.host=localhost").unwrap();
+    assert_eq!(
+      Value::Object(overrides),
+      json!({ "server": { "port": 8080, "host": "localhost" } })
+    );
+  }
+
+  #[test]
+  fn override_str_keeps_json_array_and_quoted_string_values_intact() {
+    let overrides = parse_override_str("tags=[1,2,3],name=\"hello,world\"").unwrap();
+    assert_eq!(
+      Value::Object(overrides),
+      json!({ "tags": [1, 2, 3], "name": "hello,world" })
+    );
+  }
+
+  #[test]
+  fn override_str_rejects_pairs_without_exactly_one_equals() {
+    assert!(matches!(
+      parse_override_str("server.port"),
+      Err(Error::InvalidOverridePair { .. })
+    ));
+    assert!(matches!(
+      parse_override_str("server.port=8080=9090"),
+      Err(Error::InvalidOverridePair { .. })
+    ));
+  }
+
+  #[test]
+  fn env_overlay_takes_precedence_over_file_config() {
+    let key = "CONF_TEST_ENV_OVERLAY_PRECEDENCE__SERVER__PORT";
+    // SAFETY: no other test reads or writes this process-wide env var name.
+    unsafe { std::env::set_var(key, "9090") };
+
+    let target = map(json!({ "server": { "port": 8080, "host": "localhost" } }));
+    let env = env_overlay_map("CONF_TEST_ENV_OVERLAY_PRECEDENCE__", "__").unwrap();
+    let merged = merge_objects(target, env, &MergeOptions::new(true, false).force_merge_nested())
+      .unwrap();
+
+    // SAFETY: matches the set_var above.
+    unsafe { std::env::remove_var(key) };
+
+    assert_eq!(
+      merged["server"],
+      json!({ "port": 9090, "host": "localhost" })
+    );
+  }
+
+  #[test]
+  fn merge_objects_errors_when_nested_object_overlays_a_scalar() {
+    let target = map(json!({ "database": "sqlite" }));
+    let source = map(json!({ "database": { "pool": { "size": 10 } } }));
+    let err = merge_objects(target, source, &MergeOptions::new(true, false)).unwrap_err();
+    assert!(matches!(err, Error::ObjectFieldTypeMismatch { key, .. } if key == "database"));
+  }
+
+  #[test]
+  fn merge_objects_errors_when_array_overlays_a_scalar() {
+    let target = map(json!({ "tags": "none" }));
+    let source = map(json!({ "tags": [1, 2] }));
+    let err = merge_objects(target, source, &MergeOptions::new(false, true)).unwrap_err();
+    assert!(matches!(err, Error::ArrayFieldTypeMismatch { key, .. } if key == "tags"));
+  }
+
+  #[test]
+  fn insert_nested_errors_both_ways_on_scalar_object_collision() {
+    // scalar inserted first, then nested underneath it
+    let mut target = Map::new();
+    insert_nested(&mut target, &["database".to_string()], json!(1)).unwrap();
+    assert!(matches!(
+      insert_nested(
+        &mut target,
+        &["database".to_string(), "pool".to_string()],
+        json!(2)
+      ),
+      Err(Error::ObjectFieldTypeMismatch { .. })
+    ));
+
+    // nested inserted first, then a scalar overwrite at the same leaf
+    let mut target = Map::new();
+    insert_nested(
+      &mut target,
+      &["database".to_string(), "pool".to_string()],
+      json!(1),
+    )
+    .unwrap();
+    assert!(matches!(
+      insert_nested(&mut target, &["database".to_string()], json!(2)),
+      Err(Error::ObjectFieldTypeMismatch { .. })
+    ));
+  }
+}